@@ -8,9 +8,9 @@
 //! Talon CLI commands.
 
 use clap::{Parser, Subcommand};
-use anyhow::Result;
+use anyhow::{Context, Result};
 
-use crate::TalonRegistry;
+use crate::{Capability, GitReference, Permission, Source, TalonManifest, TalonRegistry};
 
 #[derive(Parser, Debug)]
 #[command(name = "talon")]
@@ -28,10 +28,79 @@ pub enum Command {
     List,
     Search { query: String },
     Info { name: String },
-    Add { path: String },
+    Add {
+        /// A local path, or `<name>@<version-req>` to install from the
+        /// default registry.
+        target: Option<String>,
+        /// Install from a git repository instead of a path or the registry.
+        #[arg(long)]
+        git: Option<String>,
+        /// Check out a specific commit after cloning (requires --git).
+        #[arg(long)]
+        rev: Option<String>,
+        /// Check out a specific branch after cloning (requires --git).
+        #[arg(long)]
+        branch: Option<String>,
+        /// Check out a specific tag after cloning (requires --git).
+        #[arg(long)]
+        tag: Option<String>,
+    },
     Remove { name: String },
     Discover,
     Init,
+    Permission {
+        #[command(subcommand)]
+        action: PermissionAction,
+    },
+    Capability {
+        #[command(subcommand)]
+        action: CapabilityAction,
+    },
+    Verify {
+        /// Verify a single talon directory instead of the registry.
+        path: Option<String>,
+        /// Also verify talons on disk that haven't been discovered yet.
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PermissionAction {
+    /// List the permissions declared by a talon.
+    Ls { talon: String },
+    /// Declare a new permission on a talon.
+    Add { talon: String, identifier: String },
+    /// Remove a declared permission from a talon.
+    Rm { talon: String, identifier: String },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CapabilityAction {
+    /// Declare a new, empty capability bundle on a talon.
+    New { talon: String, name: String },
+}
+
+/// Load a talon's manifest by name, returning its path alongside it so
+/// callers can rewrite `TALON.md` in place.
+fn load_manifest(registry: &TalonRegistry, talon: &str) -> Result<(std::path::PathBuf, String, TalonManifest)> {
+    let entry = registry
+        .get_talon(talon)
+        .ok_or_else(|| anyhow::anyhow!("Talon '{}' not found", talon))?;
+
+    let manifest_path = entry.path.join("TALON.md");
+    let content = std::fs::read_to_string(&manifest_path)?;
+    let manifest = TalonManifest::parse(&content)?;
+
+    Ok((manifest_path, content, manifest))
+}
+
+/// Render a "not found" message, suggesting the closest installed name.
+fn not_found(registry: &TalonRegistry, name: &str) -> String {
+    match registry.suggest(name) {
+        Some(closest) => format!("Talon '{}' not found, did you mean `{}`?", name, closest),
+        None => format!("Talon '{}' not found", name),
+    }
 }
 
 pub async fn run() -> Result<()> {
@@ -71,7 +140,15 @@ pub async fn run() -> Result<()> {
         }
         
         Command::Info { name } => {
-            if let Some(t) = registry.get_talon(&name) {
+            let (base, found) = match name.split_once('@') {
+                Some((base, req)) => {
+                    let req = semver::VersionReq::parse(req)?;
+                    (base.to_string(), registry.get_talon_req(base, &req))
+                }
+                None => (name.clone(), registry.get_talon(&name)),
+            };
+
+            if let Some(t) = found {
                 println!("{}", t.name);
                 println!("Version: {}", t.version);
                 println!("Description: {}", t.description);
@@ -83,18 +160,48 @@ pub async fn run() -> Result<()> {
                 }
                 println!("Path: {}", t.path.display());
             } else {
-                println!("Talon '{}' not found", name);
+                println!("{}", not_found(&registry, &base));
             }
         }
         
-        Command::Add { path } => {
-            let name = registry.add_talon(std::path::PathBuf::from(path))?;
+        Command::Add { target, git, rev, branch, tag } => {
+            let source = if let Some(url) = git {
+                let reference = match (rev, branch, tag) {
+                    (Some(rev), None, None) => Some(GitReference::Rev(rev)),
+                    (None, Some(branch), None) => Some(GitReference::Branch(branch)),
+                    (None, None, Some(tag)) => Some(GitReference::Tag(tag)),
+                    (None, None, None) => None,
+                    _ => anyhow::bail!("Only one of --rev, --branch, --tag may be specified"),
+                };
+                Source::Git { url, reference }
+            } else {
+                let target = target
+                    .ok_or_else(|| anyhow::anyhow!("Specify a path, `name@version-req`, or --git <url>"))?;
+                match target.split_once('@') {
+                    Some((name, req)) => Source::Registry {
+                        name: name.to_string(),
+                        req: req.to_string(),
+                    },
+                    None => Source::Local(std::path::PathBuf::from(target)),
+                }
+            };
+
+            let name = registry.add_from_source(source)?;
             println!("Added talon: {}", name);
         }
         
         Command::Remove { name } => {
-            registry.remove_talon(&name)?;
-            println!("Removed talon: {}", name);
+            let (base, version) = match name.split_once('@') {
+                Some((base, version)) => (base.to_string(), Some(version.to_string())),
+                None => (name.clone(), None),
+            };
+
+            if registry.get_talon(&base).is_none() {
+                println!("{}", not_found(&registry, &base));
+            } else {
+                registry.remove_talon(&base, version.as_deref())?;
+                println!("Removed talon: {}", base);
+            }
         }
         
         Command::Discover => {
@@ -142,6 +249,112 @@ This talon can be used to greet users.
                 println!("Created example talon at {}", example.display());
             }
         }
+
+        Command::Permission { action } => match action {
+            PermissionAction::Ls { talon } => {
+                let (_, _, manifest) = load_manifest(&registry, &talon)?;
+                if manifest.permissions.is_empty() {
+                    println!("No permissions declared for '{}'", talon);
+                } else {
+                    for p in &manifest.permissions {
+                        println!(
+                            "  {} - {}",
+                            p.identifier,
+                            p.description.as_deref().unwrap_or("")
+                        );
+                        if !p.scope.is_empty() {
+                            println!("    Scope: {}", p.scope.join(", "));
+                        }
+                        if !p.commands.is_empty() {
+                            println!("    Commands: {}", p.commands.join(", "));
+                        }
+                    }
+                }
+            }
+
+            PermissionAction::Add { talon, identifier } => {
+                let (path, content, mut manifest) = load_manifest(&registry, &talon)?;
+                if manifest.permissions.iter().any(|p| p.identifier == identifier) {
+                    anyhow::bail!("Permission '{}' already declared on '{}'", identifier, talon);
+                }
+                manifest.permissions.push(Permission {
+                    identifier: identifier.clone(),
+                    description: None,
+                    scope: Vec::new(),
+                    commands: Vec::new(),
+                });
+                std::fs::write(&path, manifest.rewrite(&content)?)?;
+                println!("Added permission '{}' to '{}'", identifier, talon);
+            }
+
+            PermissionAction::Rm { talon, identifier } => {
+                let (path, content, mut manifest) = load_manifest(&registry, &talon)?;
+                let before = manifest.permissions.len();
+                manifest.permissions.retain(|p| p.identifier != identifier);
+                if manifest.permissions.len() == before {
+                    anyhow::bail!("Permission '{}' not found on '{}'", identifier, talon);
+                }
+                std::fs::write(&path, manifest.rewrite(&content)?)?;
+                println!("Removed permission '{}' from '{}'", identifier, talon);
+            }
+        },
+
+        Command::Capability { action } => match action {
+            CapabilityAction::New { talon, name } => {
+                let (path, content, mut manifest) = load_manifest(&registry, &talon)?;
+                if manifest.capabilities.iter().any(|c| c.name == name) {
+                    anyhow::bail!("Capability '{}' already exists on '{}'", name, talon);
+                }
+                manifest.capabilities.push(Capability {
+                    name: name.clone(),
+                    permissions: Vec::new(),
+                });
+                std::fs::write(&path, manifest.rewrite(&content)?)?;
+                println!("Added capability '{}' to '{}'", name, talon);
+            }
+        },
+
+        Command::Verify { path, all } => {
+            let mut manifests: Vec<(String, TalonManifest)> = Vec::new();
+
+            if let Some(path) = path {
+                let talon_md = std::path::PathBuf::from(&path).join("TALON.md");
+                let content = std::fs::read_to_string(&talon_md)
+                    .with_context(|| format!("Failed to read {}", talon_md.display()))?;
+                let manifest = TalonManifest::parse(&content)?;
+                manifests.push((manifest.name.clone(), manifest));
+            } else {
+                if all {
+                    registry.discover_talons()?;
+                }
+                for entry in registry.list_talons() {
+                    let content = std::fs::read_to_string(entry.path.join("TALON.md"))?;
+                    let manifest = TalonManifest::parse(&content)?;
+                    manifests.push((entry.name.clone(), manifest));
+                }
+            }
+
+            let mut problem_count = 0;
+            for (name, manifest) in &manifests {
+                if let Err(errors) = manifest.validate() {
+                    for error in errors {
+                        println!("{}: {}", name, error);
+                        problem_count += 1;
+                    }
+                }
+            }
+
+            if problem_count > 0 {
+                println!(
+                    "\n{} problem(s) found across {} talon(s)",
+                    problem_count,
+                    manifests.len()
+                );
+                std::process::exit(1);
+            }
+
+            println!("{} talon(s) verified successfully", manifests.len());
+        }
     }
 
     Ok(())
@@ -42,14 +42,18 @@
 //! ## Usage
 //! This talon enables FemtoClaw to interact with GitHub repositories.
 
+use std::collections::{HashMap, HashSet};
+
 use serde::{Deserialize, Serialize};
 
 pub mod cli;
 pub mod loader;
 pub mod registry;
+pub mod source;
 
 pub use loader::TalonLoader;
 pub use registry::{TalonIndex, TalonRegistry};
+pub use source::{GitReference, Source};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TalonManifest {
@@ -64,11 +68,35 @@ pub struct TalonManifest {
     pub homepage: Option<String>,
     pub runtime: Option<TalonRuntime>,
     #[serde(default)]
-    pub permissions: Vec<String>,
+    pub permissions: Vec<Permission>,
+    #[serde(default)]
+    pub capabilities: Vec<Capability>,
     #[serde(default)]
     pub environment: Vec<EnvVar>,
     #[serde(default)]
     pub commands: Vec<TalonCommand>,
+    /// Other talons this talon depends on, keyed by name with a semver
+    /// requirement string as the value (e.g. `"^1.2"`, `">=0.3, <0.5"`).
+    #[serde(default)]
+    pub dependencies: HashMap<String, String>,
+}
+
+/// A single grantable permission, scoped to the commands that need it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Permission {
+    pub identifier: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub scope: Vec<String>,
+    #[serde(default)]
+    pub commands: Vec<String>,
+}
+
+/// A named bundle of permissions that can be granted as a unit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Capability {
+    pub name: String,
+    pub permissions: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -122,4 +150,210 @@ impl TalonManifest {
 
         Ok(manifest)
     }
+
+    /// Re-serialize this manifest as the frontmatter of `content`,
+    /// preserving the markdown body after the closing `---`.
+    pub fn rewrite(&self, content: &str) -> anyhow::Result<String> {
+        let mut sections = content.splitn(3, "---");
+        let _ = sections.next();
+        let _ = sections.next();
+        let body = sections.next().unwrap_or("");
+
+        let frontmatter = serde_yaml::to_string(self)
+            .map_err(|e| anyhow::anyhow!("Failed to serialize manifest: {}", e))?;
+
+        Ok(format!("---\n{}---{}", frontmatter, body))
+    }
+
+    /// Check the manifest for structural problems: an invalid name or
+    /// version, commands or arguments with duplicate names, unknown
+    /// argument types, and required env vars that declare a default.
+    /// Collects every problem found rather than stopping at the first.
+    pub fn validate(&self) -> Result<(), Vec<ManifestError>> {
+        const KNOWN_ARG_TYPES: &[&str] = &["string", "number", "bool", "path", "enum"];
+
+        let mut errors = Vec::new();
+
+        if !is_valid_identifier(&self.name) {
+            errors.push(ManifestError::new(
+                "name",
+                format!(
+                    "'{}' must be a non-empty, lowercase identifier of letters, digits, '-' or '_', not starting with a digit",
+                    self.name
+                ),
+            ));
+        }
+
+        if let Err(e) = semver::Version::parse(&self.version) {
+            errors.push(ManifestError::new(
+                "version",
+                format!("'{}' is not a valid semver version: {}", self.version, e),
+            ));
+        }
+
+        let mut seen_commands = HashSet::new();
+        for cmd in &self.commands {
+            if !seen_commands.insert(cmd.name.as_str()) {
+                errors.push(ManifestError::new(
+                    format!("commands.{}", cmd.name),
+                    "duplicate command name",
+                ));
+            }
+
+            let mut seen_args = HashSet::new();
+            for arg in &cmd.args {
+                if !seen_args.insert(arg.name.as_str()) {
+                    errors.push(ManifestError::new(
+                        format!("commands.{}.args.{}", cmd.name, arg.name),
+                        "duplicate argument name",
+                    ));
+                }
+
+                if !KNOWN_ARG_TYPES.contains(&arg.r#type.as_str()) {
+                    errors.push(ManifestError::new(
+                        format!("commands.{}.args.{}.type", cmd.name, arg.name),
+                        format!(
+                            "unknown type '{}', expected one of: {}",
+                            arg.r#type,
+                            KNOWN_ARG_TYPES.join(", ")
+                        ),
+                    ));
+                }
+            }
+        }
+
+        for env in &self.environment {
+            if env.required && env.default.is_some() {
+                errors.push(ManifestError::new(
+                    format!("environment.{}", env.name),
+                    "a required env var cannot declare a default",
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn is_valid_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && name.starts_with(|c: char| !c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+/// A single problem found by [`TalonManifest::validate`].
+#[derive(Debug, Clone)]
+pub struct ManifestError {
+    pub field: String,
+    pub message: String,
+}
+
+impl ManifestError {
+    fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn valid_manifest() -> TalonManifest {
+        TalonManifest {
+            name: "github".to_string(),
+            version: "1.0.0".to_string(),
+            description: "GitHub integration".to_string(),
+            author: None,
+            license: None,
+            tags: Vec::new(),
+            repository: None,
+            homepage: None,
+            runtime: None,
+            permissions: Vec::new(),
+            capabilities: Vec::new(),
+            environment: Vec::new(),
+            commands: Vec::new(),
+            dependencies: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn validate_accepts_a_well_formed_manifest() {
+        assert!(valid_manifest().validate().is_ok());
+    }
+
+    #[test]
+    fn validate_flags_invalid_name_and_version() {
+        let mut manifest = valid_manifest();
+        manifest.name = "1-bad-name".to_string();
+        manifest.version = "not-a-version".to_string();
+
+        let errors = manifest.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "name"));
+        assert!(errors.iter().any(|e| e.field == "version"));
+    }
+
+    #[test]
+    fn validate_flags_duplicate_commands_and_unknown_arg_types() {
+        let mut manifest = valid_manifest();
+        manifest.commands = vec![
+            TalonCommand {
+                name: "issue".to_string(),
+                description: "Manage issues".to_string(),
+                args: vec![CommandArg {
+                    name: "id".to_string(),
+                    r#type: "number".to_string(),
+                    required: true,
+                    description: None,
+                }],
+            },
+            TalonCommand {
+                name: "issue".to_string(),
+                description: "Duplicate".to_string(),
+                args: vec![CommandArg {
+                    name: "id".to_string(),
+                    r#type: "uuid".to_string(),
+                    required: true,
+                    description: None,
+                }],
+            },
+        ];
+
+        let errors = manifest.validate().unwrap_err();
+        assert!(errors
+            .iter()
+            .any(|e| e.field == "commands.issue" && e.message == "duplicate command name"));
+        assert!(errors
+            .iter()
+            .any(|e| e.field == "commands.issue.args.id.type"));
+    }
+
+    #[test]
+    fn validate_flags_required_env_var_with_a_default() {
+        let mut manifest = valid_manifest();
+        manifest.environment.push(EnvVar {
+            name: "GH_TOKEN".to_string(),
+            required: true,
+            description: None,
+            default: Some("unset".to_string()),
+        });
+
+        let errors = manifest.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "environment.GH_TOKEN"));
+    }
 }
@@ -8,9 +8,10 @@
 //! Talon Loader - Load talons into FemtoClaw.
 
 use anyhow::Result;
+use std::collections::HashSet;
 use std::path::PathBuf;
 
-use crate::{TalonInfo, TalonManifest, TalonRegistry};
+use crate::{Permission, TalonInfo, TalonManifest, TalonRegistry};
 
 pub struct TalonLoader {
     registry: TalonRegistry,
@@ -32,10 +33,10 @@ impl TalonLoader {
     }
 
     pub fn load_talon(&self, name: &str) -> Result<TalonInfo> {
-        let entry = self
-            .registry
-            .get_talon(name)
-            .ok_or_else(|| anyhow::anyhow!("Talon '{}' not found", name))?;
+        let entry = self.registry.get_talon(name).ok_or_else(|| match self.registry.suggest(name) {
+            Some(closest) => anyhow::anyhow!("Talon '{}' not found, did you mean `{}`?", name, closest),
+            None => anyhow::anyhow!("Talon '{}' not found", name),
+        })?;
 
         let manifest_path = entry.path.join("TALON.md");
         let content = std::fs::read_to_string(&manifest_path)?;
@@ -48,14 +49,45 @@ impl TalonLoader {
         })
     }
 
+    /// Build the capabilities exposed to the model for `name`. A command is
+    /// only emitted if every permission it requires belongs to at least one
+    /// declared capability bundle, so the host runtime never offers a
+    /// command it has no way to grant least-privilege access to.
     pub fn get_capabilities(&self, name: &str) -> Result<Vec<TalonCapability>> {
         let talon = self.load_talon(name)?;
+        let manifest = &talon.manifest;
+
+        let grantable: HashSet<&str> = manifest
+            .capabilities
+            .iter()
+            .flat_map(|c| c.permissions.iter().map(String::as_str))
+            .collect();
+
         let mut capabilities = Vec::new();
 
-        for cmd in &talon.manifest.commands {
+        for cmd in &manifest.commands {
+            let required: Vec<&Permission> = manifest
+                .permissions
+                .iter()
+                .filter(|p| p.commands.iter().any(|c| c == &cmd.name))
+                .collect();
+
+            if required
+                .iter()
+                .any(|p| !grantable.contains(p.identifier.as_str()))
+            {
+                continue;
+            }
+
+            let scope: Vec<String> = required
+                .iter()
+                .flat_map(|p| p.scope.iter().cloned())
+                .collect();
+
             capabilities.push(TalonCapability {
                 name: format!("{}.{}", name, cmd.name),
                 description: cmd.description.clone(),
+                permissions: required.iter().map(|p| p.identifier.clone()).collect(),
                 args: cmd
                     .args
                     .iter()
@@ -63,6 +95,7 @@ impl TalonLoader {
                         name: a.name.clone(),
                         r#type: a.r#type.clone(),
                         required: a.required,
+                        scope: scope.clone(),
                     })
                     .collect(),
             });
@@ -71,10 +104,17 @@ impl TalonLoader {
         Ok(capabilities)
     }
 
+    /// Resolve the dependency closure of `roots`, in topological order
+    /// (dependencies before dependents).
+    pub fn resolve(&self, roots: &[String]) -> Result<Vec<String>> {
+        self.registry.resolve(roots)
+    }
+
     pub fn generate_system_prompt(&self, names: &[String]) -> Result<String> {
+        let ordered = self.registry.resolve(names)?;
         let mut prompt = String::from("Available Talons:\n\n");
 
-        for name in names {
+        for name in &ordered {
             if let Ok(talon) = self.load_talon(name) {
                 prompt.push_str(&format!(
                     "## {} (v{})\n",
@@ -108,6 +148,8 @@ impl Default for TalonLoader {
 pub struct TalonCapability {
     pub name: String,
     pub description: String,
+    /// Identifiers of the permissions required to invoke this capability.
+    pub permissions: Vec<String>,
     pub args: Vec<CapabilityArg>,
 }
 
@@ -116,4 +158,7 @@ pub struct CapabilityArg {
     pub name: String,
     pub r#type: String,
     pub required: bool,
+    /// Declared scope of the permissions backing this capability, so the
+    /// host runtime can enforce least-privilege when exposing it.
+    pub scope: Vec<String>,
 }
@@ -12,14 +12,15 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
+use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
 use walkdir::WalkDir;
 
-use crate::{TalonInfo, TalonManifest};
+use crate::{Source, TalonInfo, TalonManifest};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct TalonIndex {
-    pub talons: HashMap<String, TalonEntry>,
+    pub talons: HashMap<String, Vec<TalonEntry>>,
     pub version: String,
 }
 
@@ -33,6 +34,9 @@ pub struct TalonEntry {
     pub license: Option<String>,
     pub path: PathBuf,
     pub tags: Vec<String>,
+    #[serde(default)]
+    pub dependencies: HashMap<String, String>,
+    pub source: Source,
 }
 
 pub struct TalonRegistry {
@@ -95,8 +99,9 @@ impl TalonRegistry {
         let mut discovered = Vec::new();
 
         for entry in WalkDir::new(&self.talons_dir)
-            .max_depth(2)
+            .max_depth(3)
             .into_iter()
+            .filter_entry(|e| !Self::is_hidden_entry(e))
             .filter_map(|e| e.ok())
         {
             let path = entry.path();
@@ -114,9 +119,13 @@ impl TalonRegistry {
                                 license: manifest.license.clone(),
                                 path: talon_path.to_path_buf(),
                                 tags: manifest.tags.clone(),
+                                dependencies: manifest.dependencies.clone(),
+                                source: Source::Local(talon_path.to_path_buf()),
                             };
 
-                            self.index.talons.insert(name.clone(), entry);
+                            let versions = self.index.talons.entry(name).or_default();
+                            versions.retain(|e| e.version != entry.version);
+                            versions.push(entry);
 
                             discovered.push(TalonInfo {
                                 manifest,
@@ -133,19 +142,49 @@ impl TalonRegistry {
         Ok(discovered)
     }
 
+    /// True for dot-prefixed entries, so discovery skips internal state
+    /// such as the `.cache` directory used by [`Self::add_from_source`].
+    fn is_hidden_entry(entry: &walkdir::DirEntry) -> bool {
+        entry
+            .file_name()
+            .to_str()
+            .is_some_and(|s| s.starts_with('.'))
+    }
+
     pub fn list_talons(&self) -> Vec<&TalonEntry> {
-        self.index.talons.values().collect()
+        self.index.talons.values().flatten().collect()
     }
 
+    /// Return the highest installed version of `name`.
     pub fn get_talon(&self, name: &str) -> Option<&TalonEntry> {
-        self.index.talons.get(name)
+        self.index
+            .talons
+            .get(name)?
+            .iter()
+            .filter_map(|e| Version::parse(&e.version).ok().map(|v| (v, e)))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, e)| e)
+    }
+
+    /// Return the highest installed version of `name` satisfying `req`.
+    pub fn get_talon_req(&self, name: &str, req: &VersionReq) -> Option<&TalonEntry> {
+        self.index
+            .talons
+            .get(name)?
+            .iter()
+            .filter_map(|e| Version::parse(&e.version).ok().map(|v| (v, e)))
+            .filter(|(v, _)| req.matches(v))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, e)| e)
     }
 
     pub fn search_talons(&self, query: &str) -> Vec<&TalonEntry> {
         let query_lower = query.to_lowercase();
-        self.index
+        let results: Vec<&TalonEntry> = self
+            .index
             .talons
             .values()
+            .flatten()
             .filter(|t| {
                 t.name.to_lowercase().contains(&query_lower)
                     || t.description.to_lowercase().contains(&query_lower)
@@ -153,54 +192,252 @@ impl TalonRegistry {
                         .iter()
                         .any(|tag| tag.to_lowercase().contains(&query_lower))
             })
-            .collect()
+            .collect();
+
+        if !results.is_empty() {
+            return results;
+        }
+
+        // No substring match - fall back to the closest installed name.
+        match self.suggest(&query_lower) {
+            Some(closest) => self.index.talons.get(closest).into_iter().flatten().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Suggest the closest installed talon name to `name`, if one is
+    /// close enough to plausibly be a typo.
+    pub fn suggest(&self, name: &str) -> Option<&str> {
+        suggest(name, self.index.talons.keys().map(String::as_str))
     }
 
     pub fn add_talon(&mut self, path: PathBuf) -> Result<String> {
-        let talon_md = path.join("TALON.md");
+        self.add_from_source(Source::Local(path))
+    }
+
+    /// Install a talon from a [`Source`], fetching it into the cache
+    /// directory first if it isn't already local, then copying it into
+    /// the registry.
+    pub fn add_from_source(&mut self, source: Source) -> Result<String> {
+        let cache_dir = self.talons_dir.join(".cache");
+        let fetched = source.fetch(&cache_dir)?;
+
+        let talon_md = fetched.join("TALON.md");
         if !talon_md.exists() {
-            anyhow::bail!("TALON.md not found in {}", path.display());
+            anyhow::bail!("TALON.md not found in {}", fetched.display());
         }
 
         let content = fs::read_to_string(&talon_md)?;
         let manifest = TalonManifest::parse(&content)?;
         let name = manifest.name.clone();
 
-        let dest = self.talons_dir.join(&name);
+        let dest = self.talons_dir.join(&name).join(&manifest.version);
         if dest.exists() {
             fs::remove_dir_all(&dest)?;
         }
         fs::create_dir_all(&dest)?;
-        copy_dir_recursive(&path, &dest)?;
-
-        self.index.talons.insert(
-            name.clone(),
-            TalonEntry {
-                name: manifest.name,
-                version: manifest.version,
-                description: manifest.description,
-                author: manifest.author,
-                license: manifest.license,
-                path: dest,
-                tags: manifest.tags,
-            },
-        );
+        copy_dir_recursive(&fetched, &dest)?;
+
+        let entry = TalonEntry {
+            name: manifest.name,
+            version: manifest.version,
+            description: manifest.description,
+            author: manifest.author,
+            license: manifest.license,
+            path: dest,
+            tags: manifest.tags,
+            dependencies: manifest.dependencies,
+            source,
+        };
+
+        let versions = self.index.talons.entry(name.clone()).or_default();
+        versions.retain(|e| e.version != entry.version);
+        versions.push(entry);
 
         self.save_index()?;
         Ok(name)
     }
 
-    pub fn remove_talon(&mut self, name: &str) -> Result<()> {
-        if let Some(entry) = self.index.talons.remove(name) {
-            if entry.path.exists() {
-                fs::remove_dir_all(entry.path)?;
+    /// Remove an installed talon. If `version` is `None` and several
+    /// versions of `name` are installed, returns an error asking the
+    /// caller to disambiguate with `name@version`. If `version` is given,
+    /// it must match an installed version, or this returns an error rather
+    /// than silently doing nothing.
+    pub fn remove_talon(&mut self, name: &str, version: Option<&str>) -> Result<()> {
+        let versions = self.index.talons.get(name);
+
+        let target_version = match version {
+            Some(v) => {
+                let installed = versions.is_some_and(|versions| versions.iter().any(|e| e.version == v));
+                if !installed {
+                    anyhow::bail!("Talon '{}' version '{}' is not installed", name, v);
+                }
+                v.to_string()
+            }
+            None => {
+                let Some(versions) = versions else {
+                    return Ok(());
+                };
+                match versions.as_slice() {
+                    [] => return Ok(()),
+                    [entry] => entry.version.clone(),
+                    entries => {
+                        let installed: Vec<&str> = entries.iter().map(|e| e.version.as_str()).collect();
+                        anyhow::bail!(
+                            "Multiple versions of '{}' are installed ({}); specify one with '{}@<version>'",
+                            name,
+                            installed.join(", "),
+                            name
+                        );
+                    }
+                }
+            }
+        };
+
+        if let Some(versions) = self.index.talons.get_mut(name) {
+            if let Some(pos) = versions.iter().position(|e| e.version == target_version) {
+                let entry = versions.remove(pos);
+                if entry.path.exists() {
+                    fs::remove_dir_all(entry.path)?;
+                }
+            }
+            if versions.is_empty() {
+                self.index.talons.remove(name);
             }
-            self.save_index()?;
         }
+
+        self.save_index()?;
+        Ok(())
+    }
+
+    /// Resolve the dependency closure of `roots` against the installed
+    /// talons, returning names in topological order (dependencies before
+    /// dependents, deduplicated). Fails if a requirement is unsatisfiable,
+    /// a dependency isn't installed, or the dependency graph has a cycle.
+    pub fn resolve(&self, roots: &[String]) -> Result<Vec<String>> {
+        let mut state: HashMap<String, VisitState> = HashMap::new();
+        let mut resolved: HashMap<String, String> = HashMap::new();
+        let mut stack: Vec<String> = Vec::new();
+        let mut order: Vec<String> = Vec::new();
+
+        for root in roots {
+            let entry = self
+                .get_talon(root)
+                .ok_or_else(|| anyhow::anyhow!("Talon '{}' not found", root))?;
+            self.resolve_visit(entry, &mut state, &mut resolved, &mut stack, &mut order)?;
+        }
+
+        Ok(order)
+    }
+
+    /// Visit `entry` (the specific version a requirement, if any, already
+    /// matched) and recurse into its dependencies, each resolved against
+    /// its own requirement rather than an unconstrained "highest installed"
+    /// lookup. `resolved` tracks which version of each name has been
+    /// chosen so two dependents requiring incompatible versions of the
+    /// same talon are reported instead of silently picking one.
+    fn resolve_visit(
+        &self,
+        entry: &TalonEntry,
+        state: &mut HashMap<String, VisitState>,
+        resolved: &mut HashMap<String, String>,
+        stack: &mut Vec<String>,
+        order: &mut Vec<String>,
+    ) -> Result<()> {
+        let name = entry.name.clone();
+
+        if let Some(existing) = resolved.get(&name) {
+            if existing != &entry.version {
+                anyhow::bail!(
+                    "Conflicting requirements for '{}': already resolved to {}, but also required as {}",
+                    name,
+                    existing,
+                    entry.version
+                );
+            }
+        }
+
+        match state.get(&name) {
+            Some(VisitState::Done) => return Ok(()),
+            Some(VisitState::InProgress) => {
+                let start = stack.iter().position(|n| n == &name).unwrap_or(0);
+                let mut cycle = stack[start..].to_vec();
+                cycle.push(name.clone());
+                anyhow::bail!("Dependency cycle detected: {}", cycle.join(" -> "));
+            }
+            None => {}
+        }
+
+        resolved.insert(name.clone(), entry.version.clone());
+        state.insert(name.clone(), VisitState::InProgress);
+        stack.push(name.clone());
+
+        for (dep_name, dep_req) in &entry.dependencies {
+            let req = VersionReq::parse(dep_req).with_context(|| {
+                format!(
+                    "Invalid version requirement '{}' for dependency '{}' of talon '{}'",
+                    dep_req, dep_name, name
+                )
+            })?;
+
+            let dep_entry = self.get_talon_req(dep_name, &req).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Talon '{}' depends on '{}' {}, but no installed version satisfies it",
+                    name,
+                    dep_name,
+                    dep_req
+                )
+            })?;
+
+            self.resolve_visit(dep_entry, state, resolved, stack, order)?;
+        }
+
+        stack.pop();
+        state.insert(name.clone(), VisitState::Done);
+        order.push(name);
+
         Ok(())
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VisitState {
+    InProgress,
+    Done,
+}
+
+/// Find the candidate closest to `query` by Levenshtein edit distance,
+/// if it's within a small threshold (at most 3, or a third of the
+/// query's length, whichever is larger) of plausibly being a typo.
+fn suggest<'a>(query: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let threshold = (query.chars().count() / 3).max(3);
+
+    candidates
+        .map(|c| (edit_distance(query, c), c))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, c)| c)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr: Vec<usize> = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        curr[0] = i;
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            curr[j] = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
 impl Default for TalonRegistry {
     fn default() -> Self {
         Self::new().unwrap_or_else(|_| Self {
@@ -244,3 +481,210 @@ fn copy_dir_recursive(source: &Path, destination: &Path) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, version: &str, deps: &[(&str, &str)]) -> TalonEntry {
+        TalonEntry {
+            name: name.to_string(),
+            version: version.to_string(),
+            description: String::new(),
+            author: None,
+            license: None,
+            path: PathBuf::from(name).join(version),
+            tags: Vec::new(),
+            dependencies: deps
+                .iter()
+                .map(|(dep, req)| (dep.to_string(), req.to_string()))
+                .collect(),
+            source: Source::Local(PathBuf::from(name)),
+        }
+    }
+
+    /// Give each test its own scratch directory so `save_index` (called by
+    /// `remove_talon`) has somewhere to write, without tests interfering
+    /// with one another.
+    fn registry_with(entries: Vec<TalonEntry>) -> TalonRegistry {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let mut talons: HashMap<String, Vec<TalonEntry>> = HashMap::new();
+        for e in entries {
+            talons.entry(e.name.clone()).or_default().push(e);
+        }
+
+        let talons_dir = std::env::temp_dir().join(format!(
+            "femtoclaw-registry-test-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+        let _ = fs::create_dir_all(&talons_dir);
+
+        TalonRegistry {
+            talons_dir,
+            index: TalonIndex {
+                talons,
+                version: "1.0".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn resolve_picks_the_version_that_satisfies_the_requirement() {
+        let registry = registry_with(vec![
+            entry("a", "1.0.0", &[("b", "^1.0")]),
+            entry("b", "1.5.0", &[]),
+            entry("b", "2.0.0", &[]),
+        ]);
+
+        let order = registry.resolve(&["a".to_string()]).unwrap();
+        assert_eq!(order, vec!["b".to_string(), "a".to_string()]);
+    }
+
+    #[test]
+    fn resolve_dedupes_a_shared_transitive_dependency() {
+        // a -> b, c; b -> d; c -> d. d must be resolved once and ordered
+        // before both of its dependents.
+        let registry = registry_with(vec![
+            entry("a", "1.0.0", &[("b", "^1.0"), ("c", "^1.0")]),
+            entry("b", "1.0.0", &[("d", "^1.0")]),
+            entry("c", "1.0.0", &[("d", "^1.0")]),
+            entry("d", "1.0.0", &[]),
+        ]);
+
+        let order = registry.resolve(&["a".to_string()]).unwrap();
+        assert_eq!(order.iter().filter(|n| *n == "d").count(), 1);
+
+        let d_pos = order.iter().position(|n| n == "d").unwrap();
+        let b_pos = order.iter().position(|n| n == "b").unwrap();
+        let c_pos = order.iter().position(|n| n == "c").unwrap();
+        let a_pos = order.iter().position(|n| n == "a").unwrap();
+        assert!(d_pos < b_pos);
+        assert!(d_pos < c_pos);
+        assert!(b_pos < a_pos);
+        assert!(c_pos < a_pos);
+    }
+
+    #[test]
+    fn resolve_errors_on_conflicting_requirements() {
+        let registry = registry_with(vec![
+            entry("a", "1.0.0", &[("c", "^1.0")]),
+            entry("b", "1.0.0", &[("c", "^2.0")]),
+            entry("c", "1.0.0", &[]),
+            entry("c", "2.0.0", &[]),
+        ]);
+
+        let err = registry
+            .resolve(&["a".to_string(), "b".to_string()])
+            .unwrap_err();
+        assert!(err.to_string().contains("Conflicting requirements"));
+    }
+
+    #[test]
+    fn resolve_detects_cycles() {
+        let registry = registry_with(vec![
+            entry("a", "1.0.0", &[("b", "^1.0")]),
+            entry("b", "1.0.0", &[("a", "^1.0")]),
+        ]);
+
+        let err = registry.resolve(&["a".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("cycle"));
+    }
+
+    #[test]
+    fn resolve_errors_on_unsatisfiable_requirement() {
+        let registry = registry_with(vec![
+            entry("a", "1.0.0", &[("b", "^2.0")]),
+            entry("b", "1.0.0", &[]),
+        ]);
+
+        let err = registry.resolve(&["a".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("no installed version satisfies"));
+    }
+
+    #[test]
+    fn suggest_finds_a_close_typo() {
+        let candidates = vec!["github", "gitlab", "slack"];
+        assert_eq!(suggest("gihtub", candidates.into_iter()), Some("github"));
+    }
+
+    #[test]
+    fn suggest_returns_none_when_nothing_is_close() {
+        let candidates = vec!["github", "gitlab", "slack"];
+        assert_eq!(suggest("xyzzy", candidates.into_iter()), None);
+    }
+
+    #[test]
+    fn edit_distance_matches_known_values() {
+        assert_eq!(edit_distance("github", "github"), 0);
+        assert_eq!(edit_distance("github", "gihtub"), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn search_talons_falls_back_to_suggestion_when_no_substring_matches() {
+        let registry = registry_with(vec![entry("github", "1.0.0", &[])]);
+        let results = registry.search_talons("gihtub");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "github");
+    }
+
+    #[test]
+    fn get_talon_returns_the_highest_installed_version() {
+        let registry = registry_with(vec![
+            entry("b", "1.5.0", &[]),
+            entry("b", "2.0.0", &[]),
+            entry("b", "1.9.0", &[]),
+        ]);
+
+        assert_eq!(registry.get_talon("b").unwrap().version, "2.0.0");
+        assert!(registry.get_talon("missing").is_none());
+    }
+
+    #[test]
+    fn get_talon_req_returns_the_highest_version_satisfying_the_requirement() {
+        let registry = registry_with(vec![entry("b", "1.5.0", &[]), entry("b", "2.0.0", &[])]);
+
+        let req = VersionReq::parse("^1.0").unwrap();
+        assert_eq!(registry.get_talon_req("b", &req).unwrap().version, "1.5.0");
+
+        let req = VersionReq::parse("^3.0").unwrap();
+        assert!(registry.get_talon_req("b", &req).is_none());
+    }
+
+    #[test]
+    fn remove_talon_errors_when_the_explicit_version_is_not_installed() {
+        let mut registry = registry_with(vec![entry("github", "1.0.0", &[])]);
+
+        let err = registry.remove_talon("github", Some("9.9.9")).unwrap_err();
+        assert!(err.to_string().contains("is not installed"));
+        assert_eq!(registry.get_talon("github").unwrap().version, "1.0.0");
+    }
+
+    #[test]
+    fn remove_talon_requires_disambiguation_when_several_versions_are_installed() {
+        let mut registry = registry_with(vec![
+            entry("github", "1.0.0", &[]),
+            entry("github", "2.0.0", &[]),
+        ]);
+
+        let err = registry.remove_talon("github", None).unwrap_err();
+        assert!(err.to_string().contains("specify one with"));
+        assert_eq!(registry.list_talons().len(), 2);
+    }
+
+    #[test]
+    fn remove_talon_removes_the_given_version_when_several_are_installed() {
+        let mut registry = registry_with(vec![
+            entry("github", "1.0.0", &[]),
+            entry("github", "2.0.0", &[]),
+        ]);
+
+        registry.remove_talon("github", Some("1.0.0")).unwrap();
+        let remaining = registry.list_talons();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].version, "2.0.0");
+    }
+}
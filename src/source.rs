@@ -0,0 +1,152 @@
+// source.rs - This file is part of FemtoClaw
+// Copyright (c) 2026 FemtoClaw Developers and Contributors
+// Description:
+//     Talon Source - Remote and local sources for installing Talons.
+//     Provides fetching talons from local directories, git repositories,
+//     and the default talon registry.
+
+//! Talon sources - where a talon can be installed from.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+
+use crate::TalonManifest;
+
+/// Default git repository used to resolve talons added by name, e.g.
+/// `talon add github@^1.2`.
+pub const DEFAULT_REGISTRY_URL: &str = "https://github.com/femtoclaw/talons-registry";
+
+/// A git ref to check out after cloning.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GitReference {
+    Rev(String),
+    Branch(String),
+    Tag(String),
+}
+
+/// Where a talon is installed from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Source {
+    Local(PathBuf),
+    Git {
+        url: String,
+        reference: Option<GitReference>,
+    },
+    Registry {
+        name: String,
+        req: String,
+    },
+}
+
+impl Source {
+    /// Fetch this source into `cache_dir`, returning the directory that
+    /// contains the talon's `TALON.md`. A local source is not copied: its
+    /// own path is returned as is.
+    pub fn fetch(&self, cache_dir: &Path) -> Result<PathBuf> {
+        match self {
+            Source::Local(path) => Ok(path.clone()),
+            Source::Git { url, reference } => fetch_git(url, reference.as_ref(), cache_dir),
+            Source::Registry { name, req } => fetch_registry(name, req, cache_dir),
+        }
+    }
+}
+
+fn fetch_git(url: &str, reference: Option<&GitReference>, cache_dir: &Path) -> Result<PathBuf> {
+    fs::create_dir_all(cache_dir)?;
+
+    let dest = cache_dir.join(cache_key(url));
+    if dest.exists() {
+        fs::remove_dir_all(&dest)?;
+    }
+
+    let mut clone = Command::new("git");
+    clone.arg("clone").arg("--quiet");
+    match reference {
+        Some(GitReference::Branch(branch)) => {
+            clone.arg("--branch").arg(branch);
+        }
+        Some(GitReference::Tag(tag)) => {
+            clone.arg("--branch").arg(tag);
+        }
+        _ => {}
+    }
+    clone.arg(url).arg(&dest);
+
+    let status = clone
+        .status()
+        .with_context(|| format!("Failed to run git clone for '{}'", url))?;
+    if !status.success() {
+        anyhow::bail!("git clone of '{}' failed", url);
+    }
+
+    if let Some(GitReference::Rev(rev)) = reference {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(&dest)
+            .arg("checkout")
+            .arg("--quiet")
+            .arg(rev)
+            .status()
+            .with_context(|| format!("Failed to check out rev '{}' of '{}'", rev, url))?;
+        if !status.success() {
+            anyhow::bail!("git checkout of rev '{}' in '{}' failed", rev, url);
+        }
+    }
+
+    Ok(dest)
+}
+
+fn fetch_registry(name: &str, req: &str, cache_dir: &Path) -> Result<PathBuf> {
+    let index = fetch_git(DEFAULT_REGISTRY_URL, None, cache_dir)?;
+    let talon_dir = index.join("talons").join(name);
+    if !talon_dir.join("TALON.md").exists() {
+        anyhow::bail!("Talon '{}' not found in the registry", name);
+    }
+
+    let index_manifest = TalonManifest::parse(&fs::read_to_string(talon_dir.join("TALON.md"))?)?;
+
+    // The registry index only carries metadata used to locate a talon; if
+    // it declares its own repository, fetch the talon's actual content
+    // from there rather than from the (possibly stale) copy vendored into
+    // the index.
+    let source_dir = match &index_manifest.repository {
+        Some(url) => fetch_git(url, None, cache_dir)?,
+        None => talon_dir,
+    };
+
+    let manifest_path = source_dir.join("TALON.md");
+    let content = fs::read_to_string(&manifest_path)
+        .with_context(|| format!("TALON.md not found in '{}'", source_dir.display()))?;
+    let manifest = TalonManifest::parse(&content)?;
+
+    let version = Version::parse(&manifest.version).with_context(|| {
+        format!(
+            "Invalid version '{}' for talon '{}'",
+            manifest.version, name
+        )
+    })?;
+    let version_req =
+        VersionReq::parse(req).with_context(|| format!("Invalid version requirement '{}'", req))?;
+
+    if !version_req.matches(&version) {
+        anyhow::bail!(
+            "Talon '{}' {} does not satisfy requirement {}",
+            name,
+            version,
+            req
+        );
+    }
+
+    Ok(source_dir)
+}
+
+fn cache_key(url: &str) -> String {
+    url.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}